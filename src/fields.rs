@@ -0,0 +1,74 @@
+use eyre::WrapErr;
+
+/// A single selected column, identified by its 1-based position among a view's canonical
+/// columns (e.g. `["id", "name", "quantity"]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpec(pub usize);
+
+/// Parses a `--fields` argument such as `id,name`, `1-3`, or `2,1` into an ordered list of
+/// `FieldSpec` column selectors. Each comma-separated token is either a column name (resolved
+/// against `columns`), a single 1-based index, or an inclusive `start-end` index range.
+pub fn parse_field_spec(spec: &str, columns: &[&str]) -> eyre::Result<Vec<FieldSpec>> {
+    let mut fields = Vec::new();
+    for token in spec.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            continue;
+        }
+
+        if let Some((start, end)) = token.split_once('-') {
+            let start: usize = start
+                .trim()
+                .parse()
+                .wrap_err_with(|| format!("invalid field range: {token}"))?;
+            let end: usize = end
+                .trim()
+                .parse()
+                .wrap_err_with(|| format!("invalid field range: {token}"))?;
+            if start == 0 || end < start || end > columns.len() {
+                eyre::bail!("field range out of bounds: {token}");
+            }
+            fields.extend((start..=end).map(FieldSpec));
+        } else if let Ok(index) = token.parse::<usize>() {
+            fields.push(FieldSpec(index));
+        } else {
+            let index = columns
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(token))
+                .ok_or_else(|| eyre::eyre!("unknown field: {token}"))?;
+            fields.push(FieldSpec(index + 1));
+        }
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COLUMNS: [&str; 3] = ["id", "name", "quantity"];
+
+    #[test]
+    fn parses_names_indices_and_ranges() {
+        let fields = parse_field_spec("name,1,2-3", &COLUMNS).unwrap();
+        assert_eq!(
+            fields,
+            vec![FieldSpec(2), FieldSpec(1), FieldSpec(2), FieldSpec(3)]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_column_name() {
+        assert!(parse_field_spec("bogus", &COLUMNS).is_err());
+    }
+
+    #[test]
+    fn rejects_range_past_the_last_column() {
+        assert!(parse_field_spec("1-4000000000", &COLUMNS).is_err());
+    }
+
+    #[test]
+    fn rejects_range_starting_at_zero() {
+        assert!(parse_field_spec("0-2", &COLUMNS).is_err());
+    }
+}
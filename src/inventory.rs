@@ -0,0 +1,130 @@
+use std::fmt;
+
+use crate::item::Item;
+
+/// A quantity of a single `Item`, as it moves through parsing and storage.
+#[derive(Debug, Clone)]
+pub struct ItemStack {
+    item: Item,
+    quantity: u32,
+}
+
+impl ItemStack {
+    pub fn new(item: Item, quantity: u32) -> Self {
+        Self { item, quantity }
+    }
+
+    pub fn get_item(&self) -> &Item {
+        &self.item
+    }
+
+    pub fn size(&self) -> u32 {
+        self.quantity
+    }
+}
+
+/// A bounded container of `ItemStack`s, optionally capped at `max_size` total units.
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    max_size: Option<u32>,
+    stacks: Vec<ItemStack>,
+}
+
+impl Inventory {
+    pub fn new(max_size: Option<u32>) -> Self {
+        Self {
+            max_size,
+            stacks: Vec::new(),
+        }
+    }
+
+    pub fn total_size(&self) -> u32 {
+        self.stacks.iter().map(ItemStack::size).sum()
+    }
+
+    fn remaining_capacity(&self) -> Option<u32> {
+        self.max_size.map(|max| max.saturating_sub(self.total_size()))
+    }
+
+    pub fn max_size(&self) -> Option<u32> {
+        self.max_size
+    }
+
+    pub fn stacks(&self) -> &[ItemStack] {
+        &self.stacks
+    }
+
+    /// Whether `quantity` more units would still fit within `max_size`.
+    pub fn fits(&self, quantity: u32) -> bool {
+        match self.remaining_capacity() {
+            Some(remaining) => quantity <= remaining,
+            None => true,
+        }
+    }
+
+    /// Stores the whole stack if there is room for it, returning whether it was stored.
+    pub fn add_items(&mut self, stack: ItemStack) -> bool {
+        match self.remaining_capacity() {
+            Some(remaining) if stack.size() > remaining => false,
+            _ => {
+                self.stacks.push(stack);
+                true
+            }
+        }
+    }
+
+    /// Removes up to `quantity` units of `item_id`, across as many stacks as needed, returning
+    /// how many units were actually removed.
+    pub fn remove_items(&mut self, item_id: u32, quantity: u32) -> u32 {
+        let mut remaining = quantity;
+        self.stacks.retain_mut(|stack| {
+            if remaining == 0 || stack.item.get_id() != item_id {
+                return true;
+            }
+            if stack.quantity <= remaining {
+                remaining -= stack.quantity;
+                false
+            } else {
+                stack.quantity -= remaining;
+                remaining = 0;
+                true
+            }
+        });
+        quantity - remaining
+    }
+
+    /// Stores up to `quantity` units of `stack`'s item (never more than the stack itself holds),
+    /// bounded by remaining capacity, returning how many units were actually stored. Used to
+    /// land a partial delivery (e.g. a `move` that the destination can't fully absorb) without
+    /// losing the remainder.
+    pub fn add_bounded(&mut self, stack: ItemStack, quantity: u32) -> u32 {
+        let capped = quantity.min(stack.quantity);
+        let to_store = match self.remaining_capacity() {
+            Some(remaining) => capped.min(remaining),
+            None => capped,
+        };
+        if to_store > 0 {
+            self.stacks.push(ItemStack::new(stack.item, to_store));
+        }
+        to_store
+    }
+}
+
+impl fmt::Display for Inventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max_size {
+            Some(max) => writeln!(f, "  Capacity: {}/{}", self.total_size(), max)?,
+            None => writeln!(f, "  Capacity: {} (unbounded)", self.total_size())?,
+        }
+        for stack in self.stacks.iter() {
+            writeln!(
+                f,
+                "    {:>2} {:<12} x{}",
+                stack.get_item().get_id(),
+                stack.get_item().get_name(),
+                stack.size()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+/// A single kind of item known to the system, identified by a stable id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    id: u32,
+    name: String,
+}
+
+impl Item {
+    pub fn new(id: u32, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+        }
+    }
+
+    pub fn get_id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
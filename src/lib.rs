@@ -0,0 +1,13 @@
+pub mod fields;
+pub mod inventory;
+pub mod item;
+pub mod parser;
+pub mod policy;
+
+pub mod prelude {
+    pub use crate::fields::{parse_field_spec, FieldSpec};
+    pub use crate::inventory::{Inventory, ItemStack};
+    pub use crate::item::Item;
+    pub use crate::parser::Parser;
+    pub use crate::policy::{Decision, DefaultPolicy, StoragePolicy};
+}
@@ -1,20 +1,34 @@
-use eyre::WrapErr;
-
 use rust_inventory::parser::ParsedLine;
 use rust_inventory::prelude::*;
 
+/// Canonical columns for the "Item List" and "Storage Summary" views, in their default order.
+/// A `--fields` spec selects and reorders among these by name or 1-based position.
+const SUMMARY_COLUMNS: [&str; 3] = ["id", "name", "quantity"];
+
 fn main() -> eyre::Result<()> {
     let argv: Vec<String> = std::env::args().collect();
+    let (positional, fields_arg) = parse_args(&argv[1..]);
 
-    if argv.len() < 3 {
-        eyre::bail!("Usage: {} items_filename inventories_filename", argv[0]);
+    if positional.len() < 2 {
+        eyre::bail!(
+            "Usage: {} items_filename inventories_filename [--fields spec]",
+            argv[0]
+        );
     }
 
-    let all_items = Parser::read_from_file(&argv[1], |ins| Parser::read_items(ins))?;
+    let fields = match fields_arg {
+        Some(spec) => parse_field_spec(&spec, &SUMMARY_COLUMNS)?,
+        None => (1..=SUMMARY_COLUMNS.len()).map(FieldSpec).collect(),
+    };
+
+    let all_items = Parser::read_from_file(&positional[0], Parser::read_items)?;
     let all_inventory_lines =
-        Parser::read_from_file(&argv[2], |ins| Parser::read_inventory_lines(ins))?;
+        Parser::read_from_file(&positional[1], Parser::read_inventory_lines)?;
+
+    let policies: Vec<Box<dyn StoragePolicy>> = vec![Box::new(DefaultPolicy)];
 
-    let logged_inventories = process_inventory_requests(all_inventory_lines, &all_items);
+    let logged_inventories =
+        process_inventory_requests(all_inventory_lines, &all_items, &policies);
 
     println!("Processing Log:");
     for (entries, _) in logged_inventories.iter() {
@@ -26,73 +40,265 @@ fn main() -> eyre::Result<()> {
 
     println!("Item List:");
     for item in all_items.iter() {
-        println!("  {:>2} {}", item.get_id(), item.get_name());
+        println!("  {}", format_item_fields(item, &fields));
     }
     println!();
 
     println!("Storage Summary:");
     for (_, inv) in logged_inventories.iter() {
-        println!("{}", inv);
+        print_storage_summary(inv, &fields);
     }
 
     Ok(())
 }
 
-/// # Refactoring Approach
-/// 
-/// My approach of refactoring the process_inventory_requests() function comes from the principles of SOLID,
-/// TDD and functions should only do one thing. My goal was to reorganize this monolithic function into smaller
-/// subfunctions that achieve a single, specific task, for the purposes of better maintainabilty, readbility and testability.
-/// Note: I didn't write any new tests.
-///
-/// # Refactoring Justification
-///
-/// This function was refactored following the Single Responsibility Principle from SOLID.
-/// Each extracted function performs a single, spefific subtask, improving code readability, maintainability, and testability.
-///
-/// - process_lines(): Separates the logic for identifying inventory boundaries.
-/// - process_inventories(): Extracts inventory creation, ensuring it is isolated from processing item stacks.
-/// - log_inventories(): Manages item processing and logging, delegating specific tasks to helper functions.
-/// - process_stacks(): Encapsulates the logic for filtering and transforming `ParsedLine` entries into `ItemStack`s.
-/// - process_entries(): Handles the logic for storing or discarding items, ensuring separation of concerns.
-/// 
-/// # Other Changes
-/// 
-/// - Inside process_lines(), replaced the match line ... with matches!() for brevity.
-/// - Inside process_inventories() and process_stacks(), replaced the flat_map() with filter_map(). My 
-/// - reasoning for this is that filter_map() filters out None values while transforming valid inputs, 
-/// - making the intent clearer and avoiding unnecessary intermediate collections.
-///
-/// # Benefits of this Refactoring
-/// - Improved Readability: Each function is short and focused, making the code easier to understand.
-/// - Better Maintainability: Isolated concerns make modifying or extending functionality simpler.
-/// - Enhanced Testability: Smaller functions are easier to test individually.
-/// - Reduced Code Duplication: Extracting repeated logic into helper functions minimizes redundancy.
-///
-/// # Parameters
-/// - `all_inventory_lines`: A vector of parsed inventory-related lines.
-/// - `known_items`: A slice of known `Item`s to match against.
+/// Splits `--fields <spec>` out of the raw CLI args, returning the remaining positional args
+/// alongside the field spec, if one was given.
+fn parse_args(args: &[String]) -> (Vec<String>, Option<String>) {
+    let mut positional = Vec::new();
+    let mut fields = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--fields" {
+            fields = args.next().cloned();
+        } else {
+            positional.push(arg.clone());
+        }
+    }
+
+    (positional, fields)
+}
+
+fn format_item_fields(item: &Item, fields: &[FieldSpec]) -> String {
+    fields
+        .iter()
+        .filter_map(|FieldSpec(index)| SUMMARY_COLUMNS.get(index.wrapping_sub(1)))
+        .filter_map(|&column| match column {
+            "id" => Some(item.get_id().to_string()),
+            "name" => Some(item.get_name().to_string()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn print_storage_summary(inv: &Inventory, fields: &[FieldSpec]) {
+    match inv.max_size() {
+        Some(max) => println!("  Capacity: {}/{}", inv.total_size(), max),
+        None => println!("  Capacity: {} (unbounded)", inv.total_size()),
+    }
+
+    for stack in inv.stacks() {
+        let row: Vec<String> = fields
+            .iter()
+            .filter_map(|FieldSpec(index)| SUMMARY_COLUMNS.get(index.wrapping_sub(1)))
+            .map(|&column| match column {
+                "id" => stack.get_item().get_id().to_string(),
+                "name" => stack.get_item().get_name().to_string(),
+                "quantity" => stack.size().to_string(),
+                _ => String::new(),
+            })
+            .collect();
+        println!("    {}", row.join(" "));
+    }
+}
+
+/// Processes a full inventories file: builds each `Inventory` from its `ItemStackLine`s, logs a
+/// Stored/Discarded entry per stack via the `policies` chain, then applies any `MoveLine`
+/// instructions across the now-materialized inventories.
 ///
-/// # Returns
-/// A vector containing tuples of log entries and their corresponding `Inventory` instances.
+/// This is the eager, whole-file entry point; it collects the whole `Vec<ParsedLine>` up front
+/// and is now a thin wrapper around `stream_inventory_sections` so existing callers and tests
+/// keep working unchanged. For very large files, drive `stream_inventory_sections` directly off
+/// `Parser::stream_inventory_lines` instead, so sections can be printed and dropped one at a
+/// time instead of all being held in memory at once.
+#[cfg(not(feature = "rayon"))]
 pub fn process_inventory_requests(
     all_inventory_lines: Vec<ParsedLine>,
     known_items: &[Item],
+    policies: &[Box<dyn StoragePolicy>],
+) -> Vec<(Vec<String>, Inventory)> {
+    let moves = process_moves(&all_inventory_lines);
+
+    let mut logged_inventories: Vec<(Vec<String>, Inventory)> =
+        stream_inventory_sections(all_inventory_lines.into_iter().map(Ok), known_items, policies)
+            .collect::<eyre::Result<_>>()
+            .expect("lines already parsed into ParsedLine carry no read/parse errors");
+
+    apply_moves(known_items, &moves, &mut logged_inventories);
+
+    logged_inventories
+}
+
+/// Same contract as above, but built on `log_inventories`' `rayon`-parallel variant, which needs
+/// every inventory and its entry slice materialized up front in order to fan them out across a
+/// thread pool; that's the tradeoff for this feature over the streaming version.
+#[cfg(feature = "rayon")]
+pub fn process_inventory_requests(
+    all_inventory_lines: Vec<ParsedLine>,
+    known_items: &[Item],
+    policies: &[Box<dyn StoragePolicy>],
 ) -> Vec<(Vec<String>, Inventory)> {
     let lines = process_lines(&all_inventory_lines);
 
     let inventories = process_inventories(&all_inventory_lines);
 
-    log_inventories(known_items, lines, inventories)
+    let moves = process_moves(&all_inventory_lines);
+
+    let mut logged_inventories = log_inventories(known_items, lines, inventories, policies);
+
+    apply_moves(known_items, &moves, &mut logged_inventories);
+
+    logged_inventories
+}
+
+/// Lazily groups a stream of `ParsedLine`s into completed `(log entries, Inventory)` sections,
+/// one inventory at a time, so a caller can print-and-drop a section before the next one is even
+/// parsed. Preserves the original `split`/`skip(1)` semantics: lines before the first
+/// `InventoryLine` belong to no inventory and are dropped, and each `InventoryLine` opens a new
+/// section whose stacks are the `ItemStackLine`s that follow it.
+///
+/// `move` instructions need every inventory materialized at once (see `apply_moves`), so they
+/// are dropped here rather than supported; run a file with moves through the eager
+/// `process_inventory_requests` instead.
+#[cfg(not(feature = "rayon"))]
+fn stream_inventory_sections<'a>(
+    lines: impl Iterator<Item = eyre::Result<ParsedLine>> + 'a,
+    known_items: &'a [Item],
+    policies: &'a [Box<dyn StoragePolicy>],
+) -> impl Iterator<Item = eyre::Result<(Vec<String>, Inventory)>> + 'a {
+    SectionStream {
+        lines,
+        known_items,
+        policies,
+        state: SectionState::NotStarted,
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+enum SectionState {
+    NotStarted,
+    Pending(Inventory),
+    Done,
+}
+
+#[cfg(not(feature = "rayon"))]
+struct SectionStream<'a, I> {
+    lines: I,
+    known_items: &'a [Item],
+    policies: &'a [Box<dyn StoragePolicy>],
+    state: SectionState,
+}
+
+#[cfg(not(feature = "rayon"))]
+impl<'a, I> Iterator for SectionStream<'a, I>
+where
+    I: Iterator<Item = eyre::Result<ParsedLine>>,
+{
+    type Item = eyre::Result<(Vec<String>, Inventory)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut inv = match std::mem::replace(&mut self.state, SectionState::Done) {
+            SectionState::Done => return None,
+            SectionState::Pending(inv) => inv,
+            SectionState::NotStarted => loop {
+                match self.lines.next() {
+                    Some(Ok(ParsedLine::InventoryLine { max_size })) => {
+                        break Inventory::new(max_size)
+                    }
+                    Some(Ok(_)) => continue,
+                    Some(Err(err)) => return Some(Err(err)),
+                    None => return None,
+                }
+            },
+        };
+
+        let mut section_lines = Vec::new();
+        loop {
+            match self.lines.next() {
+                Some(Ok(ParsedLine::InventoryLine { max_size })) => {
+                    self.state = SectionState::Pending(Inventory::new(max_size));
+                    break;
+                }
+                Some(Ok(line)) => section_lines.push(line),
+                Some(Err(err)) => return Some(Err(err)),
+                None => break,
+            }
+        }
+
+        let stacks_to_store = process_stacks(self.known_items, &section_lines);
+        let entries = process_entries(stacks_to_store, &mut inv, self.policies);
+        Some(Ok((entries, inv)))
+    }
+}
+
+fn process_moves(all_inventory_lines: &[ParsedLine]) -> Vec<(u32, u32, usize, usize)> {
+    all_inventory_lines
+        .iter()
+        .filter_map(|line| match line {
+            ParsedLine::MoveLine {
+                quantity,
+                item_id,
+                src,
+                dst,
+            } => Some((*quantity, *item_id, *src, *dst)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Applies `move` instructions now that every inventory has been materialized, so a move can
+/// freely reach across sections that `log_inventories` processed independently. Delivery is
+/// capped at whatever the destination can actually hold (`add_bounded`), and any undelivered
+/// remainder is put back in the source so units are never lost.
+fn apply_moves(
+    known_items: &[Item],
+    moves: &[(u32, u32, usize, usize)],
+    logged_inventories: &mut [(Vec<String>, Inventory)],
+) {
+    for &(quantity, item_id, src, dst) in moves {
+        if src == 0 || dst == 0 || src > logged_inventories.len() || dst > logged_inventories.len() {
+            continue;
+        }
+
+        let Some(item) = known_items.iter().find(|item| item.get_id() == item_id) else {
+            continue;
+        };
+        let item_name = item.get_name().to_string();
+
+        let removed = logged_inventories[src - 1].1.remove_items(item_id, quantity);
+        let delivered = if removed > 0 {
+            logged_inventories[dst - 1]
+                .1
+                .add_bounded(ItemStack::new(item.clone(), removed), removed)
+        } else {
+            0
+        };
+        if delivered < removed {
+            logged_inventories[src - 1]
+                .1
+                .add_bounded(ItemStack::new(item.clone(), removed - delivered), removed - delivered);
+        }
+
+        let message = if delivered == quantity {
+            format!("Moved ({delivered}) {item_name} from {src} to {dst}")
+        } else {
+            format!("Partial ({delivered}/{quantity}) {item_name} from {src} to {dst}")
+        };
+        logged_inventories[src - 1].0.push(message);
+    }
 }
 
+#[cfg(feature = "rayon")]
 fn process_lines(
-    all_inventory_lines: &Vec<ParsedLine>,
+    all_inventory_lines: &[ParsedLine],
 ) -> std::slice::Split<'_, ParsedLine, impl FnMut(&ParsedLine) -> bool> {
     all_inventory_lines.split(|line| matches!(line, ParsedLine::InventoryLine { .. }))
 }
 
-fn process_inventories(all_inventory_lines: &Vec<ParsedLine>) -> Vec<Inventory> {
+#[cfg(feature = "rayon")]
+fn process_inventories(all_inventory_lines: &[ParsedLine]) -> Vec<Inventory> {
     let inventories: Vec<Inventory> = all_inventory_lines
         .iter()
         .filter_map(|line| match line {
@@ -103,24 +309,32 @@ fn process_inventories(all_inventory_lines: &Vec<ParsedLine>) -> Vec<Inventory>
     inventories
 }
 
+/// Each `(Inventory, entries)` pair is processed independently of every other pair, so this runs
+/// the work across a rayon thread pool. `known_items` is read-only and shared by reference, and
+/// each closure owns its `Inventory` outright, so the bodies are already `Send`-safe; we just
+/// collect the `(Inventory, entries)` pairs into an indexed `Vec` first so `into_par_iter` can
+/// fan them out while `collect` puts the results back in original order.
+#[cfg(feature = "rayon")]
 fn log_inventories(
     known_items: &[Item],
     lines: std::slice::Split<'_, ParsedLine, impl FnMut(&ParsedLine) -> bool>,
     inventories: Vec<Inventory>,
+    policies: &[Box<dyn StoragePolicy>],
 ) -> Vec<(Vec<String>, Inventory)> {
-    let logged_inventories: Vec<(_, Inventory)> = inventories
-        .into_iter()
-        .zip(lines.skip(1))
+    use rayon::prelude::*;
+
+    let pairs: Vec<(Inventory, &[ParsedLine])> = inventories.into_iter().zip(lines.skip(1)).collect();
+
+    pairs
+        .into_par_iter()
         .map(|(mut inv, entries)| {
             let stacks_to_store = process_stacks(known_items, entries);
 
-            let entries = process_entries(stacks_to_store, &mut inv);
+            let entries = process_entries(stacks_to_store, &mut inv, policies);
 
             (entries, inv)
         })
-        .collect();
-
-    logged_inventories
+        .collect()
 }
 
 fn process_stacks(known_items: &[Item], entries: &[ParsedLine]) -> Vec<ItemStack> {
@@ -137,21 +351,184 @@ fn process_stacks(known_items: &[Item], entries: &[ParsedLine]) -> Vec<ItemStack
     stacks_to_store
 }
 
-fn process_entries(stacks_to_store: Vec<ItemStack>, inv: &mut Inventory) -> Vec<String> {
+fn process_entries(
+    stacks_to_store: Vec<ItemStack>,
+    inv: &mut Inventory,
+    policies: &[Box<dyn StoragePolicy>],
+) -> Vec<String> {
     let entries: Vec<String> = stacks_to_store
         .into_iter()
         .map(|stack| {
+            let (label, stored) = match evaluate_policies(policies, &stack, inv) {
+                Decision::Store => (
+                    if inv.add_items(stack.clone()) {
+                        "Stored"
+                    } else {
+                        "Discarded"
+                    },
+                    stack.size(),
+                ),
+                Decision::Discard => ("Discarded", 0),
+                Decision::StorePartial(quantity) => {
+                    let stored = inv.add_bounded(stack.clone(), quantity);
+                    (if stored > 0 { "Stored" } else { "Discarded" }, stored)
+                }
+            };
             format!(
                 "{:9} ({:>2}) {}",
-                if inv.add_items(stack.clone()) {
-                    "Stored"
-                } else {
-                    "Discarded"
-                },
-                stack.size(),
+                label,
+                stored,
                 stack.get_item().get_name()
             )
         })
         .collect();
     entries
 }
+
+/// Consults each policy in order: any `Discard` wins outright, otherwise the tightest
+/// `StorePartial` cap applies, otherwise the stack is stored in full.
+fn evaluate_policies(
+    policies: &[Box<dyn StoragePolicy>],
+    stack: &ItemStack,
+    inv: &Inventory,
+) -> Decision {
+    let mut decision = Decision::Store;
+    for policy in policies {
+        match policy.decide(stack, inv) {
+            Decision::Discard => return Decision::Discard,
+            Decision::StorePartial(quantity) => {
+                decision = match decision {
+                    Decision::StorePartial(current) => Decision::StorePartial(current.min(quantity)),
+                    _ => Decision::StorePartial(quantity),
+                };
+            }
+            Decision::Store => {}
+        }
+    }
+    decision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: u32, name: &str) -> Item {
+        Item::new(id, name)
+    }
+
+    #[test]
+    fn apply_moves_moves_full_quantity_when_room_allows() {
+        let widget = item(7, "Widget");
+        let mut src = Inventory::new(Some(10));
+        src.add_items(ItemStack::new(widget.clone(), 5));
+        let dst = Inventory::new(Some(10));
+        let mut logged = vec![(Vec::new(), src), (Vec::new(), dst)];
+
+        apply_moves(&[widget], &[(3, 7, 1, 2)], &mut logged);
+
+        assert_eq!(logged[0].1.total_size(), 2);
+        assert_eq!(logged[1].1.total_size(), 3);
+        assert_eq!(logged[0].0, vec!["Moved (3) Widget from 1 to 2"]);
+    }
+
+    #[test]
+    fn apply_moves_conserves_units_when_destination_is_full() {
+        let widget = item(7, "Widget");
+        let mut src = Inventory::new(Some(10));
+        src.add_items(ItemStack::new(widget.clone(), 5));
+        let mut dst = Inventory::new(Some(2));
+        dst.add_items(ItemStack::new(widget.clone(), 2));
+        let mut logged = vec![(Vec::new(), src), (Vec::new(), dst)];
+
+        apply_moves(&[widget], &[(3, 7, 1, 2)], &mut logged);
+
+        // Destination has no room at all, so nothing is delivered and the source keeps all 5.
+        assert_eq!(logged[0].1.total_size(), 5);
+        assert_eq!(logged[1].1.total_size(), 2);
+        assert_eq!(logged[0].0, vec!["Partial (0/3) Widget from 1 to 2"]);
+    }
+
+    #[test]
+    fn apply_moves_reports_partial_when_source_lacks_stock() {
+        let widget = item(7, "Widget");
+        let mut src = Inventory::new(None);
+        src.add_items(ItemStack::new(widget.clone(), 1));
+        let dst = Inventory::new(None);
+        let mut logged = vec![(Vec::new(), src), (Vec::new(), dst)];
+
+        apply_moves(&[widget], &[(3, 7, 1, 2)], &mut logged);
+
+        assert_eq!(logged[0].1.total_size(), 0);
+        assert_eq!(logged[1].1.total_size(), 1);
+        assert_eq!(logged[0].0, vec!["Partial (1/3) Widget from 1 to 2"]);
+    }
+
+    struct AlwaysDiscard;
+    impl StoragePolicy for AlwaysDiscard {
+        fn decide(&self, _stack: &ItemStack, _inv: &Inventory) -> Decision {
+            Decision::Discard
+        }
+    }
+
+    struct CapAt(u32);
+    impl StoragePolicy for CapAt {
+        fn decide(&self, _stack: &ItemStack, _inv: &Inventory) -> Decision {
+            Decision::StorePartial(self.0)
+        }
+    }
+
+    #[test]
+    fn evaluate_policies_discard_wins_outright() {
+        let policies: Vec<Box<dyn StoragePolicy>> = vec![Box::new(CapAt(5)), Box::new(AlwaysDiscard)];
+        let inv = Inventory::new(None);
+        let stack = ItemStack::new(item(1, "Thing"), 10);
+
+        assert_eq!(evaluate_policies(&policies, &stack, &inv), Decision::Discard);
+    }
+
+    #[test]
+    fn evaluate_policies_applies_tightest_partial_cap() {
+        let policies: Vec<Box<dyn StoragePolicy>> = vec![Box::new(CapAt(5)), Box::new(CapAt(2))];
+        let inv = Inventory::new(None);
+        let stack = ItemStack::new(item(1, "Thing"), 10);
+
+        assert_eq!(
+            evaluate_policies(&policies, &stack, &inv),
+            Decision::StorePartial(2)
+        );
+    }
+
+    #[test]
+    fn evaluate_policies_stores_in_full_with_no_restricting_policy() {
+        let policies: Vec<Box<dyn StoragePolicy>> = vec![Box::new(DefaultPolicy)];
+        let inv = Inventory::new(None);
+        let stack = ItemStack::new(item(1, "Thing"), 10);
+
+        assert_eq!(evaluate_policies(&policies, &stack, &inv), Decision::Store);
+    }
+
+    #[test]
+    #[cfg(not(feature = "rayon"))]
+    fn section_stream_drops_lines_before_first_inventory_and_opens_sections_on_boundaries() {
+        let lines = vec![
+            ParsedLine::ItemStackLine { id: 1, quantity: 9 }, // belongs to no inventory
+            ParsedLine::InventoryLine { max_size: None },
+            ParsedLine::ItemStackLine { id: 1, quantity: 2 },
+            ParsedLine::InventoryLine { max_size: Some(5) },
+            ParsedLine::ItemStackLine { id: 1, quantity: 3 },
+        ];
+        let known_items = vec![item(1, "Thing")];
+        let policies: Vec<Box<dyn StoragePolicy>> = vec![Box::new(DefaultPolicy)];
+
+        let sections: Vec<(Vec<String>, Inventory)> =
+            stream_inventory_sections(lines.into_iter().map(Ok), &known_items, &policies)
+                .collect::<eyre::Result<_>>()
+                .unwrap();
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].1.max_size(), None);
+        assert_eq!(sections[0].1.total_size(), 2);
+        assert_eq!(sections[1].1.max_size(), Some(5));
+        assert_eq!(sections[1].1.total_size(), 3);
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Lines};
+use std::path::Path;
+
+use eyre::WrapErr;
+
+use crate::item::Item;
+
+/// A single line read from an inventories file, already classified by shape.
+#[derive(Debug, Clone)]
+pub enum ParsedLine {
+    InventoryLine { max_size: Option<u32> },
+    ItemStackLine { id: u32, quantity: u32 },
+    /// A `move <quantity> of <item_id> from <src> to <dst>` instruction, where `src`/`dst` are
+    /// 1-indexed positions among the `InventoryLine`s in the file.
+    MoveLine {
+        quantity: u32,
+        item_id: u32,
+        src: usize,
+        dst: usize,
+    },
+}
+
+pub struct Parser;
+
+impl Parser {
+    /// Opens `path` and hands a line iterator to `f`, which does the actual parsing.
+    pub fn read_from_file<P, F, T>(path: P, f: F) -> eyre::Result<T>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(Lines<BufReader<File>>) -> eyre::Result<T>,
+    {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        f(BufReader::new(file).lines())
+    }
+
+    /// Parses an items file: one `id name` pair per line.
+    pub fn read_items(lines: Lines<BufReader<File>>) -> eyre::Result<Vec<Item>> {
+        let mut items = Vec::new();
+        for line in lines {
+            let line = line.wrap_err("failed to read items line")?;
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.split_whitespace();
+            let id: u32 = parts
+                .next()
+                .ok_or_else(|| eyre::eyre!("missing item id in line: {line}"))?
+                .parse()
+                .wrap_err_with(|| format!("invalid item id in line: {line}"))?;
+            let name = parts.collect::<Vec<_>>().join(" ");
+            items.push(Item::new(id, name));
+        }
+        Ok(items)
+    }
+
+    /// Parses an inventories file into a flat stream of `InventoryLine`/`ItemStackLine` entries.
+    ///
+    /// Each `inventory <max_size>` line opens a new section; every `id quantity` line after it,
+    /// up until the next `inventory` line, belongs to that section. Collects the whole file
+    /// before returning; for very large files prefer `stream_inventory_lines`.
+    pub fn read_inventory_lines(lines: Lines<BufReader<File>>) -> eyre::Result<Vec<ParsedLine>> {
+        Self::stream_inventory_lines(lines).collect()
+    }
+
+    /// Like `read_inventory_lines`, but parses and yields one `ParsedLine` at a time instead of
+    /// collecting the whole file into memory first.
+    pub fn stream_inventory_lines(
+        lines: Lines<BufReader<File>>,
+    ) -> impl Iterator<Item = eyre::Result<ParsedLine>> {
+        lines.filter_map(|line| {
+            let parse = || -> eyre::Result<Option<ParsedLine>> {
+                let line = line.wrap_err("failed to read inventory line")?;
+                Self::parse_inventory_line(line.trim())
+            };
+            parse().transpose()
+        })
+    }
+
+    fn parse_inventory_line(line: &str) -> eyre::Result<Option<ParsedLine>> {
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("inventory") => {
+                let max_size = parts.next().and_then(|s| s.parse().ok());
+                Ok(Some(ParsedLine::InventoryLine { max_size }))
+            }
+            Some("move") => {
+                let quantity: u32 = parts
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("missing quantity in line: {line}"))?
+                    .parse()
+                    .wrap_err_with(|| format!("invalid quantity in line: {line}"))?;
+                parts.next(); // "of"
+                let item_id: u32 = parts
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("missing item id in line: {line}"))?
+                    .parse()
+                    .wrap_err_with(|| format!("invalid item id in line: {line}"))?;
+                parts.next(); // "from"
+                let src: usize = parts
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("missing source inventory in line: {line}"))?
+                    .parse()
+                    .wrap_err_with(|| format!("invalid source inventory in line: {line}"))?;
+                parts.next(); // "to"
+                let dst: usize = parts
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("missing destination inventory in line: {line}"))?
+                    .parse()
+                    .wrap_err_with(|| format!("invalid destination inventory in line: {line}"))?;
+                Ok(Some(ParsedLine::MoveLine {
+                    quantity,
+                    item_id,
+                    src,
+                    dst,
+                }))
+            }
+            Some(id) => {
+                let id: u32 = id
+                    .parse()
+                    .wrap_err_with(|| format!("invalid item id in line: {line}"))?;
+                let quantity: u32 = parts
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("missing quantity in line: {line}"))?
+                    .parse()
+                    .wrap_err_with(|| format!("invalid quantity in line: {line}"))?;
+                Ok(Some(ParsedLine::ItemStackLine { id, quantity }))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use crate::inventory::{Inventory, ItemStack};
+
+/// What a `StoragePolicy` wants to happen to a candidate stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Store,
+    Discard,
+    StorePartial(u32),
+}
+
+/// A rule that a processor consults before a stack is stored, so storage behavior can be
+/// composed (e.g. a per-item-type cap, a banned-id filter, a fill-to-80%-capacity rule)
+/// instead of being hardcoded into the processing loop.
+pub trait StoragePolicy: Send + Sync {
+    fn decide(&self, stack: &ItemStack, inv: &Inventory) -> Decision;
+}
+
+/// The original behavior, kept as the default policy: store the whole stack if it fits,
+/// otherwise discard it.
+pub struct DefaultPolicy;
+
+impl StoragePolicy for DefaultPolicy {
+    fn decide(&self, stack: &ItemStack, inv: &Inventory) -> Decision {
+        if inv.fits(stack.size()) {
+            Decision::Store
+        } else {
+            Decision::Discard
+        }
+    }
+}